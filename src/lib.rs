@@ -1,5 +1,136 @@
-use chess::{Board, ChessMove, Color, File, Game, GameResult, MoveGen, Piece, Rank, Square, EMPTY};
+use chess::{
+    Board, BoardStatus, ChessMove, Color, File, Game, GameResult, MoveGen, Piece, Rank, Square,
+    EMPTY,
+};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
+mod pst;
+
+/// Search budget for [`MyChess::make_ai_move_with_config`].
+///
+/// Leave a field `None` to not bound the search by that dimension. Iterative deepening checks
+/// both limits before starting each depth and stops once either is reached, returning the best
+/// move found by the last fully completed depth. A depth already under way is not interrupted,
+/// so the search can run past `max_time`.
+#[derive(Clone, Copy, Debug)]
+pub struct AiConfig {
+    /// The deepest iteration the search is allowed to start.
+    pub max_depth: Option<u8>,
+    /// The wall-clock budget for the whole search, starting from the first iteration.
+    pub max_time: Option<Duration>,
+}
+
+impl Default for AiConfig {
+    /// Matches the engine's original fixed-depth-4 behavior.
+    fn default() -> Self {
+        AiConfig {
+            max_depth: Some(4),
+            max_time: None,
+        }
+    }
+}
+
+/// The kind of bound a transposition table entry represents, relative to the
+/// alpha-beta window it was computed with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    /// The stored score is the exact evaluation of the node.
+    Exact,
+    /// The stored score is a lower bound (a beta cutoff occurred).
+    Lower,
+    /// The stored score is an upper bound (no move improved alpha).
+    Upper,
+}
+
+/// A single cached evaluation for a position, keyed by its Zobrist hash.
+#[derive(Clone, Copy)]
+struct TtEntry {
+    /// The remaining search depth the score was computed at.
+    depth: u8,
+    /// The evaluation score, relative to the side to move.
+    score: i32,
+    /// Whether `score` is exact or a bound on the true value.
+    bound: Bound,
+}
+
+/// Transposition table mapping a position's Zobrist hash to its cached
+/// evaluation, shared across an entire top-level search so that
+/// transpositions reached via different move orders are only searched once.
+type TranspositionTable = HashMap<u64, TtEntry>;
+
+/// Two killer-move slots per remaining search depth: quiet moves that caused a beta cutoff at
+/// that depth in a sibling node, tried before the rest of the quiet moves since a move that
+/// cut off once in a position is likely to cut off again in a similar one.
+type KillerTable = HashMap<u8, [Option<ChessMove>; 2]>;
+
+/// Records `mov` as a killer move at `depth`, keeping the two most recent distinct killers.
+fn store_killer(killers: &mut KillerTable, depth: u8, mov: ChessMove) {
+    let slots = killers.entry(depth).or_insert([None, None]);
+    if slots[0] != Some(mov) {
+        slots[1] = slots[0];
+        slots[0] = Some(mov);
+    }
+}
+
+/// A single search position, reused across an entire search tree.
+///
+/// `chess::Board` is cheap to copy, so instead of cloning a whole `Game` (and its move
+/// history) at every node, the search pushes the board reached by a move onto `stack` and
+/// makes it current, then pops `stack` to restore the parent position. `push`/`pop` must be
+/// called symmetrically around the recursive call for a move.
+struct Node {
+    /// The position currently being searched.
+    board: Board,
+    /// Prior positions on the current search path, most recent last.
+    stack: Vec<Board>,
+    /// Zobrist hashes of positions already played in the real game leading up to the search
+    /// root, used together with `stack` to detect repetitions reached mid-search.
+    game_history: Vec<u64>,
+}
+
+impl Node {
+    /// Starts a new search rooted at `board`, with an empty undo stack. `game_history` should
+    /// contain the hashes of every position played so far in the real game, including `board`
+    /// itself.
+    fn new(board: Board, game_history: Vec<u64>) -> Self {
+        Node {
+            board,
+            stack: Vec::new(),
+            game_history,
+        }
+    }
+
+    /// Applies `mov`, pushing the current position so it can be restored by [`Self::pop`].
+    fn push(&mut self, mov: ChessMove) {
+        let next = self.board.make_move_new(mov);
+        self.stack.push(self.board);
+        self.board = next;
+    }
+
+    /// Restores the position saved by the matching [`Self::push`].
+    fn pop(&mut self) {
+        self.board = self
+            .stack
+            .pop()
+            .expect("pop called without a matching push");
+    }
+
+    /// Counts how many times a position with `hash` has occurred so far: in the real game
+    /// history, on the current search path, and the current position itself.
+    fn repetition_count(&self, hash: u64) -> usize {
+        let history_hits = self.game_history.iter().filter(|&&h| h == hash).count();
+        // The first board on the stack is the search root, already the last entry of
+        // `game_history`, so it is skipped here to avoid counting it twice.
+        let path_hits = self
+            .stack
+            .iter()
+            .skip(1)
+            .filter(|board| board.get_hash() == hash)
+            .count();
+        history_hits + path_hits + 1
+    }
+}
 
 /// Represents a MyChess game.
 ///
@@ -9,6 +140,9 @@ pub struct MyChess {
     pub board: Board,
     pub color: Color,
     pub game: Game,
+    /// Zobrist hashes of every position played so far, current position last. Used to detect
+    /// and avoid steering the search into threefold-repetition draws.
+    pub position_history: Vec<u64>,
 }
 
 /// Creates a new `MyChess` instance with default settings.
@@ -29,10 +163,12 @@ impl MyChess {
     /// struct and extracting the initial MyChess board, current player's color, and game state.
     pub fn new() -> Self {
         let game = Game::new();
+        let board = game.current_position();
         MyChess {
-            board: game.current_position(),
+            board,
             color: game.side_to_move(),
             game,
+            position_history: vec![board.get_hash()],
         }
     }
 
@@ -58,6 +194,7 @@ impl MyChess {
         let game = Game::new();
         self.board = game.current_position();
         self.color = game.side_to_move();
+        self.position_history = vec![self.board.get_hash()];
         game
     }
 
@@ -74,6 +211,9 @@ impl MyChess {
         let result = self.game.make_move(mov);
         self.board = self.game.current_position();
         self.color = self.game.side_to_move();
+        if result {
+            self.position_history.push(self.board.get_hash());
+        }
         result
     }
 
@@ -108,46 +248,145 @@ impl MyChess {
         MoveGen::new_legal(&self.board)
     }
 
-    /// Makes a MyChess move using an AI strategy, updating the game state and current board.
+    /// Makes a MyChess move using the AI's default search budget (a fixed depth of 4).
     ///
-    /// This method generates and evaluates possible MyChess moves using a simple AI strategy,
-    /// considering capturing moves first and then non-capturing moves. The AI performs a
-    /// limited-depth search to evaluate potential future positions and chooses the move
-    /// with the highest evaluation score. The internal state of the `MyChess` struct is then
-    /// updated with the chosen move.
-
+    /// See [`Self::make_ai_move_with_config`] for a version that accepts a depth/time budget.
     pub fn make_ai_move(&mut self) {
-        let mut iter = self.move_iterator();
-        let mut move_results: Vec<(ChessMove, i32)> = Vec::new();
-
-        // lets iterate over targets.
-        let targets = self.board.color_combined(!self.board.side_to_move());
-        iter.set_iterator_mask(*targets);
-
-        for mov in &mut iter {
-            // This move captures one of my opponents pieces (with the exception of en passant)
-            let mut new_game = self.game.clone();
-            new_game.make_move(mov);
-            let result = Self::make_ai_move_r(&new_game, 4, i32::MIN + 1, i32::MAX);
-            move_results.push((mov, result));
-        }
+        self.make_ai_move_with_config(AiConfig::default());
+    }
 
-        // now, iterate over the rest of the moves
-        iter.set_iterator_mask(!EMPTY);
-        for mov in &mut iter {
-            // This move does not capture anything
-            let mut new_game = self.game.clone();
-            new_game.make_move(mov);
-            let result = Self::make_ai_move_r(&new_game, 4, i32::MIN + 1, i32::MAX);
-            move_results.push((mov, result));
+    /// Makes a MyChess move using an AI strategy, updating the game state and current board.
+    ///
+    /// This runs [`Self::search_best_move`] with an empty iteration callback and applies
+    /// whatever move it settles on.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The depth/time budget controlling how far iterative deepening is allowed to go.
+    pub fn make_ai_move_with_config(&mut self, config: AiConfig) {
+        if let Some(mov) = self.search_best_move(config, |_depth, _score, _mov| {}) {
+            self.make_move(mov);
         }
+    }
+
+    /// Searches for the best move in the current position without applying it, using
+    /// iterative deepening.
+    ///
+    /// This searches depth 1, then 2, 3, ... reusing the previous iteration's best move as the
+    /// first move tried at the root (so alpha-beta cutoffs kick in earlier). Before each depth it
+    /// stops if that depth exceeds `config.max_depth` or `config.max_time` has elapsed; a depth
+    /// already under way runs to completion. Within each
+    /// iteration, captures are still searched before quiet moves. `on_iteration` is called with
+    /// `(depth, score, best_move)` after each depth completes, so callers (e.g. a UCI front-end)
+    /// can report search progress as it happens.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The depth/time budget controlling how far iterative deepening is allowed to go.
+    /// * `on_iteration` - Called after each completed depth with that depth's score and best move.
+    ///
+    /// # Returns
+    ///
+    /// The best move found by the last fully completed iteration, or `None` if there are no
+    /// legal moves.
+    pub fn search_best_move(
+        &mut self,
+        config: AiConfig,
+        mut on_iteration: impl FnMut(u8, i32, ChessMove),
+    ) -> Option<ChessMove> {
+        let start = Instant::now();
+        let mut tt: TranspositionTable = TranspositionTable::new();
+        let mut killers: KillerTable = KillerTable::new();
+        let mut best_move: Option<ChessMove> = None;
+        let mut depth: u8 = 1;
+
+        loop {
+            if let Some(max_depth) = config.max_depth {
+                if depth > max_depth {
+                    break;
+                }
+            }
+            if let Some(max_time) = config.max_time {
+                if start.elapsed() >= max_time {
+                    break;
+                }
+            }
+
+            let mut node = Node::new(self.board, self.position_history.clone());
+            let mut move_results: Vec<(ChessMove, i32)> = Vec::new();
+            // `make_ai_move_r` scores a child from its side to move (the opponent), so each root
+            // result is negated to score it for the side making the move.
+
+            // Try the previous iteration's best move first so alpha-beta cuts off sooner.
+            if let Some(mov) = best_move {
+                node.push(mov);
+                let result = -Self::make_ai_move_r(
+                    &mut node,
+                    depth,
+                    i32::MIN + 1,
+                    i32::MAX,
+                    &mut tt,
+                    &mut killers,
+                );
+                node.pop();
+                move_results.push((mov, result));
+            }
+
+            let mut iter = self.move_iterator();
+            let targets = self.board.color_combined(!self.board.side_to_move());
+            iter.set_iterator_mask(*targets);
+            for mov in &mut iter {
+                // This move captures one of my opponents pieces (with the exception of en passant)
+                if Some(mov) == best_move {
+                    continue;
+                }
+                node.push(mov);
+                let result = -Self::make_ai_move_r(
+                    &mut node,
+                    depth,
+                    i32::MIN + 1,
+                    i32::MAX,
+                    &mut tt,
+                    &mut killers,
+                );
+                node.pop();
+                move_results.push((mov, result));
+            }
+
+            // now, iterate over the rest of the moves
+            iter.set_iterator_mask(!EMPTY);
+            for mov in &mut iter {
+                // This move does not capture anything
+                if Some(mov) == best_move {
+                    continue;
+                }
+                node.push(mov);
+                let result = -Self::make_ai_move_r(
+                    &mut node,
+                    depth,
+                    i32::MIN + 1,
+                    i32::MAX,
+                    &mut tt,
+                    &mut killers,
+                );
+                node.pop();
+                move_results.push((mov, result));
+            }
 
-        if !move_results.is_empty() {
+            if move_results.is_empty() {
+                break;
+            }
             move_results.sort_by(|(_, res1), (_, res2)| res2.cmp(res1));
-            self.game.make_move(move_results[0].0);
-            self.board = self.game.current_position();
-            self.color = self.game.side_to_move();
+            best_move = Some(move_results[0].0);
+            on_iteration(depth, move_results[0].1, move_results[0].0);
+
+            if depth == u8::MAX {
+                break;
+            }
+            depth += 1;
         }
+
+        best_move
     }
 
     /// Performs a recursive evaluation of a MyChess move using a minimax algorithm with alpha-beta pruning.
@@ -158,39 +397,78 @@ impl MyChess {
     ///
     /// # Arguments
     ///
-    /// * `game` - The current game state.
+    /// * `node` - The current search position; moves are applied and undone against it in place.
     /// * `depth` - The remaining depth of the search tree.
     /// * `a` - The alpha value (lower bound) for alpha-beta pruning.
     /// * `b` - The beta value (upper bound) for alpha-beta pruning.
+    /// * `tt` - The transposition table shared across the whole top-level search.
+    /// * `killers` - The killer-move table shared across the whole top-level search.
     ///
     /// # Returns
     ///
     /// An evaluation score for the specified MyChess move.
-    pub fn make_ai_move_r(game: &Game, depth: u8, mut a: i32, b: i32) -> i32 {
+    fn make_ai_move_r(
+        node: &mut Node,
+        depth: u8,
+        mut a: i32,
+        mut b: i32,
+        tt: &mut TranspositionTable,
+        killers: &mut KillerTable,
+    ) -> i32 {
         let mut result1: i32 = i32::MIN + 1;
         let mut result: i32;
-        let board = game.current_position();
-        let mut game_over: bool = false;
-        if let Some(result) = game.result() {
-            if result == GameResult::BlackCheckmates
-                || result == GameResult::WhiteCheckmates
-                || result == GameResult::Stalemate
-            {
-                game_over = true;
+        let board = node.board;
+        let hash = board.get_hash();
+
+        // A position reached for the third time is a draw; check this before the transposition
+        // table, since whether a hash is a repetition depends on the path taken to reach it and
+        // so can't be cached alongside the hash alone.
+        if node.repetition_count(hash) >= 3 {
+            return 0;
+        }
+
+        let alpha_orig = a;
+        let beta_orig = b;
+
+        if let Some(entry) = tt.get(&hash) {
+            if entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower => a = a.max(entry.score),
+                    Bound::Upper => b = b.min(entry.score),
+                }
+                if a >= b {
+                    return entry.score;
+                }
             }
         }
+
+        let game_over = board.status() != BoardStatus::Ongoing;
         // Terminal cases
         if depth == 0 || game_over {
-            return Self::evaluate_board(&board, game.side_to_move());
+            return Self::evaluate_board(&board, board.side_to_move());
         } else {
             let mut iter = MoveGen::new_legal(&board);
             let targets = board.color_combined(!board.side_to_move());
             iter.set_iterator_mask(*targets);
 
-            for mov in &mut iter {
-                // This move captures one of the opponents pieces (no en passant)
-                let new_game = Self::make_move_r(game.clone(), mov);
-                result = -Self::make_ai_move_r(&new_game, depth - 1, -b, -a);
+            // Order captures by MVV-LVA (Most Valuable Victim / Least Valuable Attacker) so the
+            // strongest captures are searched first, giving alpha-beta cutoffs more of a chance
+            // to fire early.
+            let mut captures: Vec<ChessMove> = iter.by_ref().collect();
+            captures.sort_by_key(|mov| {
+                let victim = board
+                    .piece_on(mov.get_dest())
+                    .map_or(0, |piece| Self::piece_value(&piece));
+                let attacker = board
+                    .piece_on(mov.get_source())
+                    .map_or(0, |piece| Self::piece_value(&piece));
+                -(victim * 10 - attacker)
+            });
+            for mov in captures {
+                node.push(mov);
+                result = -Self::make_ai_move_r(node, depth - 1, -b, -a, tt, killers);
+                node.pop();
                 result1 = result1.max(result);
                 a = a.max(result);
                 if a >= b {
@@ -199,61 +477,120 @@ impl MyChess {
             }
 
             iter.set_iterator_mask(!EMPTY);
-            for mov in &mut iter {
+            let mut quiet_moves: Vec<ChessMove> = iter.collect();
+            if let Some(slots) = killers.get(&depth) {
+                let mut ordered = Vec::with_capacity(quiet_moves.len());
+                for killer in slots.iter().flatten() {
+                    if let Some(pos) = quiet_moves.iter().position(|mov| mov == killer) {
+                        ordered.push(quiet_moves.remove(pos));
+                    }
+                }
+                ordered.extend(quiet_moves);
+                quiet_moves = ordered;
+            }
+            for mov in quiet_moves {
                 // This move does not capture anything
-                let new_game = Self::make_move_r(game.clone(), mov);
-                result = -Self::make_ai_move_r(&new_game, depth - 1, -b, -a);
+                node.push(mov);
+                result = -Self::make_ai_move_r(node, depth - 1, -b, -a, tt, killers);
+                node.pop();
                 result1 = result1.max(result);
                 a = a.max(result);
                 if a >= b {
+                    store_killer(killers, depth, mov);
                     break;
                 }
             }
         }
+
+        let bound = if result1 <= alpha_orig {
+            Bound::Upper
+        } else if result1 >= beta_orig {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        tt.insert(
+            hash,
+            TtEntry {
+                depth,
+                score: result1,
+                bound,
+            },
+        );
+
         result1
     }
 
-    /// Evaluates the current MyChess board position by calculating the material advantage for a specified color.
+    /// Evaluates the current MyChess board position using material plus a tapered
+    /// piece-square-table score, relative to a specified color.
     ///
-    /// This function calculates the material advantage for a given color based on the piece values on the
-    /// MyChess board. It considers the values of pawns, knights, bishops, rooks, and queens for both players,
-    /// and returns a numerical score representing the material advantage for the specified color.
+    /// Material is combined with positional value from [`pst::piece_square_value`], blended by
+    /// the game phase computed from [`pst::phase_weight`] (see [`pst`]) so that the engine plays
+    /// opening/middlegame principles (center control, king safety) early and switches toward
+    /// endgame-appropriate placement (active king, advanced pawns) as material is
+    /// traded off.
     ///
     /// # Arguments
     ///
     /// * `board` - The current MyChess board position.
-    /// * `color` - The color for which the material advantage is calculated.
+    /// * `color` - The color for which the score is calculated.
     ///
     /// # Returns
     ///
-    /// A numerical score representing the material advantage for the specified color.
+    /// A numerical score representing the advantage for the specified color.
     fn evaluate_board(board: &Board, color: Color) -> i32 {
-        let mut white_pieces = 0;
-        let mut black_pieces = 0;
+        let mut white_material = 0;
+        let mut black_material = 0;
+        let mut white_mg = 0;
+        let mut white_eg = 0;
+        let mut black_mg = 0;
+        let mut black_eg = 0;
+        let mut phase = 0;
 
         for rank in 0..8 {
             for file in 0..8 {
                 let square = Square::make_square(Rank::from_index(rank), File::from_index(file));
                 if let Some(piece) = board.piece_on(square) {
-                    match board.color_on(square) {
-                        Some(Color::White) => white_pieces += Self::piece_value(&piece),
-                        Some(Color::Black) => black_pieces += Self::piece_value(&piece),
-                        None => {}
+                    if let Some(piece_color) = board.color_on(square) {
+                        let (mg, eg) = pst::piece_square_value(piece, square, piece_color);
+                        phase += pst::phase_weight(piece);
+                        match piece_color {
+                            Color::White => {
+                                white_material += Self::piece_value(&piece);
+                                white_mg += mg;
+                                white_eg += eg;
+                            }
+                            Color::Black => {
+                                black_material += Self::piece_value(&piece);
+                                black_mg += mg;
+                                black_eg += eg;
+                            }
+                        }
                     }
                 }
             }
         }
+
+        let phase = phase.min(pst::TOTAL_PHASE);
+        let mg_score = white_mg - black_mg;
+        let eg_score = white_eg - black_eg;
+        let positional =
+            (mg_score * phase + eg_score * (pst::TOTAL_PHASE - phase)) / pst::TOTAL_PHASE;
+        let material = white_material - black_material;
+        let score = material + positional;
+
         if color == Color::White {
-            return white_pieces - black_pieces;
+            return score;
         }
-        black_pieces - white_pieces
+        -score
     }
 
-    /// Retrieves the numerical value associated with a MyChess piece.
+    /// Retrieves the numerical value associated with a MyChess piece, in centipawns.
     ///
     /// This function returns the numerical value associated with a MyChess piece based on traditional piece values
-    /// used for evaluation. The values assigned are: pawn (1), knight (3), bishop (3), rook (5), queen (9), and king (0).
-    /// You may want to adjust the value for the king based on the game state or specific evaluation criteria.
+    /// used for evaluation. The values assigned are: pawn (100), knight (300), bishop (300), rook (500), queen (900),
+    /// and king (0). You may want to adjust the value for the king based on the game state or specific evaluation
+    /// criteria.
     ///
     /// # Arguments
     ///
@@ -265,11 +602,11 @@ impl MyChess {
     ///
     fn piece_value(piece: &Piece) -> i32 {
         match piece {
-            Piece::Pawn => 1,
-            Piece::Knight => 3,
-            Piece::Bishop => 3,
-            Piece::Rook => 5,
-            Piece::Queen => 9,
+            Piece::Pawn => 100,
+            Piece::Knight => 300,
+            Piece::Bishop => 300,
+            Piece::Rook => 500,
+            Piece::Queen => 900,
             Piece::King => 0, // You might want to adjust this based on the game state
         }
     }
@@ -295,4 +632,4 @@ impl MyChess {
         }
         game_over
     }
-}
\ No newline at end of file
+}
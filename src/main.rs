@@ -1,32 +1,115 @@
-use chess::ChessMove;
-use std::io;
+use chess::{Board, ChessMove, Game};
+use my_chess::{AiConfig, MyChess};
+use std::io::{self, BufRead, Write};
 use std::str::FromStr;
-use my_chess::MyChess;
+use std::time::Duration;
+
+/// Number of fields in a FEN string (piece placement, side to move, castling rights,
+/// en passant target, halfmove clock, fullmove number).
+const FEN_FIELDS: usize = 6;
 
 fn main() {
     let mut chess = MyChess::new();
-    chess.print_board();
-    while !chess.is_game_over() {
-        // Read user input
-        println!("Enter your move (e.g., e2e4):");
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line");
-
-        // Parse user input into a Move
-        match ChessMove::from_str(input.trim()) {
-            Ok(chess_move) => {
-                // Apply the move to the board
-                chess.make_move(chess_move);
-                chess.print_board();
+    let stdin = io::stdin();
+
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("uci") => {
+                println!("id name MyChess");
+                println!("id author juicysteak7");
+                println!("uciok");
             }
-            Err(_) => {
-                println!("Invalid move. Please enter a move in UCI format (e.g., e2e4).");
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                chess.game = chess.new_game();
             }
+            Some("position") => handle_position(&mut chess, tokens.collect()),
+            Some("go") => handle_go(&mut chess, tokens.collect()),
+            Some("quit") => break,
+            _ => {}
         }
-        println!("AI is thinking....");
-        chess.make_ai_move();
-        chess.print_board();
+        io::stdout().flush().ok();
+    }
+}
+
+/// Handles a UCI `position [startpos|fen <fen>] moves <move>...` command, setting up the
+/// board and replaying the listed moves.
+fn handle_position(chess: &mut MyChess, args: Vec<&str>) {
+    if args.is_empty() {
+        return;
+    }
+
+    let moves_idx = match args[0] {
+        "startpos" => {
+            chess.game = chess.new_game();
+            1
+        }
+        "fen" => {
+            let fen_len = FEN_FIELDS.min(args.len() - 1);
+            let fen = args[1..1 + fen_len].join(" ");
+            if let Ok(board) = Board::from_str(&fen) {
+                chess.game = Game::new_with_board(board);
+                chess.board = chess.game.current_position();
+                chess.color = chess.game.side_to_move();
+                chess.position_history = vec![chess.board.get_hash()];
+            }
+            1 + fen_len
+        }
+        _ => return,
+    };
+
+    if args.get(moves_idx) == Some(&"moves") {
+        for mov_str in &args[moves_idx + 1..] {
+            if let Ok(mov) = ChessMove::from_str(mov_str) {
+                chess.make_move(mov);
+            }
+        }
+    }
+}
+
+/// Handles a UCI `go [depth <n>] [movetime <ms>]` command: runs the iterative-deepening
+/// search, emitting an `info` line per completed depth, then prints `bestmove`.
+fn handle_go(chess: &mut MyChess, args: Vec<&str>) {
+    let mut max_depth: Option<u8> = None;
+    let mut max_time: Option<Duration> = None;
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg {
+            "depth" => max_depth = iter.next().and_then(|v| v.parse().ok()),
+            "movetime" => {
+                max_time = iter
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .map(Duration::from_millis)
+            }
+            _ => {}
+        }
+    }
+
+    let config = if max_depth.is_none() && max_time.is_none() {
+        AiConfig::default()
+    } else {
+        AiConfig {
+            max_depth,
+            max_time,
+        }
+    };
+
+    let best_move = chess.search_best_move(config, |depth, score, mov| {
+        println!("info depth {depth} score cp {score} pv {mov}");
+    });
+
+    if let Some(mov) = best_move {
+        chess.make_move(mov);
+        println!("bestmove {mov}");
+    } else {
+        println!("bestmove 0000");
     }
 }